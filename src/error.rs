@@ -0,0 +1,150 @@
+//! `error` module contains error structures and auxiliary helpers that CDRS returns
+//! whenever something goes wrong while talking to Cassandra or parsing its protocol.
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::string::FromUtf8Error;
+#[cfg(feature = "std")]
+use std::string::FromUtf8Error;
+
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+#[cfg(feature = "std")]
+use std::fmt;
+#[cfg(not(feature = "std"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(not(feature = "std"))]
+use core_io as io;
+#[cfg(feature = "std")]
+use std::result;
+#[cfg(not(feature = "std"))]
+use core::result;
+
+/// CDRS result type which is used through the whole project. It's basically a `Result`
+/// with  CDRS `Error`.
+pub type Result<T> = result::Result<T, Error>;
+
+/// CDRS custom error type. Represents parsing errors encountered while decoding native
+/// protocol frames via `Error::Parse`, IO and UTF-8 errors via `Error::Io` and
+/// `Error::Utf8`, and other errors via `Error::General`.
+#[derive(Debug)]
+pub enum Error {
+    /// General error.
+    General(String),
+    /// Internal IO error.
+    Io(io::Error),
+    /// Internal error that may happen when parsing UTF8 strings.
+    Utf8(FromUtf8Error),
+    /// An error which occurs when a frame that is being decoded is malformed:
+    /// truncated, has an invalid length prefix or otherwise violates the
+    /// native protocol's wire format.
+    Parse(ParseError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::General(ref msg) => write!(f, "{}", msg),
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Utf8(ref err) => write!(f, "{}", err),
+            Error::Parse(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+// `std::error::Error` isn't available without the standard library, so this impl
+// (and the `description()` calls it enables) only exists with the `std` feature.
+#[cfg(feature = "std")]
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::General(ref msg) => msg.as_str(),
+            Error::Io(ref err) => err.description(),
+            Error::Utf8(ref err) => err.description(),
+            Error::Parse(ref err) => err.description(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Error {
+        Error::Utf8(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        Error::Parse(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(err: String) -> Error {
+        Error::General(err)
+    }
+}
+
+impl<'a> From<&'a str> for Error {
+    fn from(err: &'a str) -> Error {
+        Error::General(err.to_string())
+    }
+}
+
+/// Describes a malformed native protocol frame: a length prefix, a primitive body or a
+/// type tag that does not match what was actually available on the wire.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// A read ran off the end of the available bytes, e.g. a `[string]`'s declared
+    /// length claims more bytes than the cursor has left.
+    UnexpectedEof {
+        /// Name of the field being decoded when the short read happened.
+        field: &'static str,
+        /// Number of bytes the decoder needed to finish reading `field`.
+        expected: u64,
+        /// Number of bytes that were actually available.
+        available: u64,
+    },
+    /// A value did not have the shape required by its Cassandra type, e.g. an
+    /// out-of-range enum discriminant or an invalid `[bytes]` length.
+    InvalidType {
+        /// Name of the field being decoded.
+        field: &'static str,
+        /// Human readable explanation of what was wrong with the value.
+        reason: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::UnexpectedEof { field, expected, available } => {
+                write!(f,
+                       "unexpected eof while reading '{}': expected {} bytes, {} available",
+                       field,
+                       expected,
+                       available)
+            }
+            ParseError::InvalidType { field, ref reason } => {
+                write!(f, "invalid value for '{}': {}", field, reason)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdError for ParseError {
+    fn description(&self) -> &str {
+        match *self {
+            ParseError::UnexpectedEof { .. } => "unexpected eof while reading a protocol frame",
+            ParseError::InvalidType { .. } => "invalid value for a protocol frame field",
+        }
+    }
+}
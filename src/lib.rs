@@ -1,5 +1,13 @@
 //! **cdrs** is a native Cassandra DB client written in Rust.
 //! It's under a hard development as of now.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+extern crate core_io;
+
 extern crate snap;
 extern crate byteorder;
 #[macro_use]
@@ -10,19 +18,34 @@ extern crate uuid;
 extern crate openssl;
 extern crate r2d2;
 
-use std::io::Cursor;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(not(feature = "std"))]
+use core_io::Read;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use error::Result as CDRSResult;
 
 pub mod frame;
 pub mod types;
 
-pub mod authenticators;
-pub mod client;
 pub mod compression;
-pub mod connection_manager;
 pub mod consistency;
 pub mod error;
-pub mod events;
 pub mod query;
+
+// These modules talk directly to a socket and pull in `std::net`, so they only
+// make sense (and only build) with the `std` feature enabled.
+#[cfg(feature = "std")]
+pub mod authenticators;
+#[cfg(feature = "std")]
+pub mod client;
+#[cfg(feature = "std")]
+pub mod connection_manager;
+#[cfg(feature = "std")]
+pub mod events;
+#[cfg(feature = "std")]
 pub mod transport;
 
 
@@ -30,12 +53,22 @@ pub mod transport;
 pub trait IntoBytes {
     /// It should convert a struct into an array of bytes.
     fn into_cbytes(&self) -> Vec<u8>;
+
+    /// Appends this value's wire representation directly onto `out`, instead of
+    /// allocating a fresh `Vec` the way `into_cbytes` does. The default
+    /// implementation just falls back to `into_cbytes` for backwards
+    /// compatibility; types on hot encoding paths (primitives making up most of a
+    /// frame's body) should override it to append in place.
+    fn serialize(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.into_cbytes().as_slice());
+    }
 }
 
 /// `FromBytes` should be used to parse an array of bytes into a structure.
 pub trait FromBytes {
-    /// It gets and array of bytes and should return an implementor struct.
-    fn from_bytes(Vec<u8>) -> Self;
+    /// It gets and array of bytes and should return an implementor struct, or a
+    /// `ParseError` if the bytes do not make up a well-formed value.
+    fn from_bytes(Vec<u8>) -> CDRSResult<Self> where Self: Sized;
 }
 
 /// `AsBytes` should be used to convert a value into a single byte.
@@ -51,9 +84,15 @@ pub trait FromSingleByte {
     fn from_byte(u8) -> Self;
 }
 
-/// `FromCursor` should be used to get parsed structure from an `io:Cursor`
-/// wich bound to an array of bytes.
+/// `FromCursor` should be used to get a parsed structure from any `Read` source,
+/// e.g. an `io::Cursor` over an owned buffer, a borrowed slice, or a socket. This
+/// lets the type layer parse incrementally off a transport instead of requiring
+/// the whole response body to be collected into a `Vec` first. Decoding a
+/// malformed or truncated frame should return a `ParseError` rather than
+/// panicking, so a single bad server response does not bring down the whole
+/// process.
 pub trait FromCursor {
-    /// It should return an implementor from an `io::Cursor` over an array of bytes.
-    fn from_cursor(&mut Cursor<Vec<u8>>) -> Self;
+    /// It should return an implementor from a `Read` source over an array of
+    /// bytes, or a `ParseError` describing why the bytes could not be decoded.
+    fn from_cursor<R: Read>(&mut R) -> CDRSResult<Self> where Self: Sized;
 }
@@ -5,12 +5,31 @@ pub const SHORT_LEN: usize = 2;
 pub const INT_LEN: usize = 4;
 pub const UUID_LEN: usize = 16;
 
+#[cfg(feature = "std")]
+use std::collections::HashMap as Map;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as Map;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
 use std::io;
+#[cfg(not(feature = "std"))]
+use core_io as io;
+#[cfg(feature = "std")]
 use std::io::{Cursor, Read};
+#[cfg(not(feature = "std"))]
+use core_io::{Cursor, Read};
+// `[inet]` needs an actual socket address type, which only `std::net` provides —
+// so `CInet` itself is gated behind the `std` feature further down.
+#[cfg(feature = "std")]
 use std::net::SocketAddr;
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt, ByteOrder};
+use byteorder::{BigEndian, ByteOrder};
 use {FromBytes, IntoBytes, FromCursor};
-use error::Result as CDRSResult;
+use error::{Error, ParseError, Result as CDRSResult};
+#[cfg(feature = "std")]
 use types::data_serialization_types::decode_inet;
 
 pub mod data_serialization_types;
@@ -31,10 +50,30 @@ pub trait IntoRustByName<R> {
     fn get_by_name(&self, name: &str) -> Option<CDRSResult<R>>;
 }
 
+/// Writes the raw `SHORT_LEN`/`INT_LEN`-sized big-endian length prefixes used by
+/// `serialize()` directly onto `out`, without the intermediate `Vec` allocation
+/// `to_short`/`to_int` need to return their own buffer. Built on `ByteOrder`'s
+/// slice-based methods rather than `WriteBytesExt`, since the latter only works
+/// over an `io::Write` and `core_io` does not implement that for `Vec<u8>`.
+fn extend_be_u16(out: &mut Vec<u8>, v: u16) {
+    let mut buf = [0u8; SHORT_LEN];
+    BigEndian::write_u16(&mut buf, v);
+    out.extend_from_slice(&buf);
+}
+
+fn extend_be_i32(out: &mut Vec<u8>, v: i32) {
+    let mut buf = [0u8; INT_LEN];
+    BigEndian::write_i32(&mut buf, v);
+    out.extend_from_slice(&buf);
+}
+
 /// Tries to converts u64 numerical value into array of n bytes.
 pub fn try_to_n_bytes(int: u64, n: usize) -> io::Result<Vec<u8>> {
-    let mut bytes = vec![];
-    try!(bytes.write_uint::<BigEndian>(int, n));
+    let mut bytes = Vec::with_capacity(n);
+    unsafe {
+        bytes.set_len(n);
+    }
+    BigEndian::write_uint(&mut bytes, int, n);
 
     return Ok(bytes);
 }
@@ -58,55 +97,59 @@ pub fn i_to_n_bytes(int: i64, n: usize) -> Vec<u8> {
     return try_i_to_n_bytes(int, n).unwrap();
 }
 
-///
+/// Decodes a big-endian integer straight off the byte slice via `ByteOrder`,
+/// rather than through a `Cursor` + `ReadBytesExt`: the latter needs an `io::Read`
+/// impl, which `core_io` doesn't provide for `Vec<u8>`/slices, while `ByteOrder`'s
+/// methods work on any `&[u8]` regardless of the `std` feature.
 pub fn try_from_bytes(bytes: Vec<u8>) -> Result<u64, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_uint::<BigEndian>(bytes.len());
+    return Ok(BigEndian::read_uint(&bytes, bytes.len()));
 }
 
 ///
 pub fn try_u16_from_bytes(bytes: Vec<u8>) -> Result<u16, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_u16::<BigEndian>();
+    return Ok(BigEndian::read_u16(&bytes));
 }
 
 ///
 pub fn try_i_from_bytes(bytes: Vec<u8>) -> Result<i64, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_int::<BigEndian>(bytes.len());
+    return Ok(BigEndian::read_int(&bytes, bytes.len()));
 }
 
 ///
 pub fn try_i32_from_bytes(bytes: Vec<u8>) -> Result<i32, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_i32::<BigEndian>();
+    return Ok(BigEndian::read_i32(&bytes));
 }
 
 ///
 pub fn try_f32_from_bytes(bytes: Vec<u8>) -> Result<f32, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_f32::<BigEndian>();
+    return Ok(BigEndian::read_f32(&bytes));
 }
 
 ///
 pub fn try_f64_from_bytes(bytes: Vec<u8>) -> Result<f64, io::Error> {
-    let mut c = Cursor::new(bytes.clone());
-    return c.read_f64::<BigEndian>();
+    return Ok(BigEndian::read_f64(&bytes));
 }
 
-/// Converts byte-array into u64
-pub fn from_bytes(bytes: Vec<u8>) -> u64 {
-    return try_from_bytes(bytes).unwrap();
+/// Converts byte-array into u64. Returns a `ParseError` rather than panicking when
+/// `bytes` does not hold a well-formed big-endian integer.
+pub fn from_bytes(bytes: Vec<u8>) -> CDRSResult<u64> {
+    if bytes.len() == 0 || bytes.len() > 8 {
+        return Err(Error::from(ParseError::InvalidType {
+            field: "integer",
+            reason: format!("{} bytes is not a valid integer width", bytes.len()),
+        }));
+    }
+    return Ok(try!(try_from_bytes(bytes)));
 }
 
-/// Converts byte-array into i64
-pub fn from_i_bytes(bytes: Vec<u8>) -> i64 {
-    return try_i_from_bytes(bytes).unwrap();
+/// Converts byte-array into i64.
+pub fn from_i_bytes(bytes: Vec<u8>) -> CDRSResult<i64> {
+    return Ok(try!(try_i_from_bytes(bytes)));
 }
 
-/// Converts byte-array into u16
-pub fn from_u16_bytes(bytes: Vec<u8>) -> u16 {
-    return try_u16_from_bytes(bytes).unwrap();
+/// Converts byte-array into u16.
+pub fn from_u16_bytes(bytes: Vec<u8>) -> CDRSResult<u16> {
+    return Ok(try!(try_u16_from_bytes(bytes)));
 }
 
 /// Converts number u64 into Cassandra's [short].
@@ -119,7 +162,7 @@ pub fn to_int(int: i64) -> Vec<u8> {
     return i_to_n_bytes(int, INT_LEN);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CString {
     string: String,
 }
@@ -156,17 +199,23 @@ impl IntoBytes for CString {
         v.extend_from_slice(self.string.as_bytes());
         return v;
     }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        extend_be_u16(out, self.string.len() as u16);
+        out.extend_from_slice(self.string.as_bytes());
+    }
 }
 
 impl FromCursor for CString {
     /// from_cursor gets Cursor who's position is set such that it should be a start of a [string].
     /// It reads required number of bytes and returns a String
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CString {
-        let len_bytes = cursor_next_value(&mut cursor, SHORT_LEN as u64);
-        let len: u64 = from_bytes(len_bytes.to_vec());
-        let body_bytes = cursor_next_value(&mut cursor, len);
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CString> {
+        let len_bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "CString len"));
+        let len: u64 = try!(from_bytes(len_bytes.to_vec()));
+        let body_bytes = try!(cursor_next_value(&mut cursor, len, "CString body"));
+        let string = try!(String::from_utf8(body_bytes));
 
-        return CString { string: String::from_utf8(body_bytes).unwrap() };
+        return Ok(CString { string: string });
     }
 }
 
@@ -202,17 +251,23 @@ impl IntoBytes for CStringLong {
         v.extend_from_slice(self.string.as_bytes());
         return v;
     }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        extend_be_i32(out, self.string.len() as i32);
+        out.extend_from_slice(self.string.as_bytes());
+    }
 }
 
 impl FromCursor for CStringLong {
     /// from_cursor gets Cursor who's position is set such that it should be a start of a [string].
     /// It reads required number of bytes and returns a String
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CStringLong {
-        let len_bytes = cursor_next_value(&mut cursor, INT_LEN as u64);
-        let len: u64 = from_bytes(len_bytes.to_vec());
-        let body_bytes = cursor_next_value(&mut cursor, len);
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CStringLong> {
+        let len_bytes = try!(cursor_next_value(&mut cursor, INT_LEN as u64, "CStringLong len"));
+        let len: u64 = try!(from_bytes(len_bytes.to_vec()));
+        let body_bytes = try!(cursor_next_value(&mut cursor, len, "CStringLong body"));
+        let string = try!(String::from_utf8(body_bytes));
 
-        return CStringLong { string: String::from_utf8(body_bytes).unwrap() };
+        return Ok(CStringLong { string: string });
     }
 }
 
@@ -246,48 +301,202 @@ impl IntoBytes for CStringList {
 
         return bytes;
     }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        extend_be_u16(out, self.list.len() as u16);
+        for cstring in self.list.iter() {
+            cstring.serialize(out);
+        }
+    }
 }
 
 impl FromCursor for CStringList {
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CStringList {
-        let mut len_bytes = [0; SHORT_LEN];
-        if let Err(err) = cursor.read(&mut len_bytes) {
-            error!("Read Cassandra bytes error: {}", err);
-            panic!(err);
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CStringList> {
+        let len_bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "CStringList len"));
+        let len: u64 = try!(from_bytes(len_bytes));
+        let mut list = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            list.push(try!(CString::from_cursor(&mut cursor)));
+        }
+        return Ok(CStringList { list: list });
+    }
+}
+
+/// Cassandra `[string map]`: a `[short]` count followed by that many key/value
+/// `[string]` pairs. Used by STARTUP and similar frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CStringMap {
+    pub map: Map<String, String>,
+}
+
+impl CStringMap {
+    pub fn new(map: Map<String, String>) -> CStringMap {
+        return CStringMap { map: map };
+    }
+}
+
+impl IntoBytes for CStringMap {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut bytes = to_short(self.map.len() as u64);
+        for (key, value) in self.map.iter() {
+            bytes.extend_from_slice(CString::new(key.clone()).into_cbytes().as_slice());
+            bytes.extend_from_slice(CString::new(value.clone()).into_cbytes().as_slice());
+        }
+        return bytes;
+    }
+}
+
+impl FromCursor for CStringMap {
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CStringMap> {
+        let len_bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "CStringMap len"));
+        let len: u64 = try!(from_bytes(len_bytes));
+        // `Map::with_capacity` isn't available on `BTreeMap` (the `no_std` backend),
+        // so the count only drives the loop bound, not a capacity hint.
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = try!(CString::from_cursor(&mut cursor)).into_plain();
+            let value = try!(CString::from_cursor(&mut cursor)).into_plain();
+            map.insert(key, value);
+        }
+        return Ok(CStringMap { map: map });
+    }
+}
+
+/// Cassandra `[string multimap]`: a `[short]` count followed by that many pairs of
+/// a key `[string]` and a value `[string list]`. Used e.g. by the SUPPORTED frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CStringMultimap {
+    pub map: Map<String, Vec<String>>,
+}
+
+impl CStringMultimap {
+    pub fn new(map: Map<String, Vec<String>>) -> CStringMultimap {
+        return CStringMultimap { map: map };
+    }
+}
+
+impl IntoBytes for CStringMultimap {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut bytes = to_short(self.map.len() as u64);
+        for (key, values) in self.map.iter() {
+            bytes.extend_from_slice(CString::new(key.clone()).into_cbytes().as_slice());
+            let list = CStringList {
+                list: values.iter().map(|v| CString::new(v.clone())).collect(),
+            };
+            bytes.extend_from_slice(list.into_cbytes().as_slice());
         }
-        let len: u64 = from_bytes(len_bytes.to_vec());
-        let list = (0..len).map(|_| CString::from_cursor(&mut cursor)).collect();
-        return CStringList { list: list };
+        return bytes;
+    }
+}
+
+impl FromCursor for CStringMultimap {
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CStringMultimap> {
+        let len_bytes = try!(cursor_next_value(&mut cursor,
+                                                SHORT_LEN as u64,
+                                                "CStringMultimap len"));
+        let len: u64 = try!(from_bytes(len_bytes));
+        // `Map::with_capacity` isn't available on `BTreeMap` (the `no_std` backend),
+        // so the count only drives the loop bound, not a capacity hint.
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = try!(CString::from_cursor(&mut cursor)).into_plain();
+            let values = try!(CStringList::from_cursor(&mut cursor)).into_plain();
+            map.insert(key, values);
+        }
+        return Ok(CStringMultimap { map: map });
     }
 }
 
 //
 
-#[derive(Debug, Clone)]
-/// The structure that represents Cassandra byte type
+/// `[bytes]` length that marks a Cassandra `NULL` value.
+const NULL_INT_LEN: CInt = -1;
+/// `[bytes]` length that marks an unset (`NOT_SET`) bind marker, i.e. "leave the
+/// existing value untouched" for a prepared statement parameter.
+const NOT_SET_INT_LEN: CInt = -2;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The structure that represents Cassandra byte type. Besides an actual payload it
+/// can represent a `NULL` value or, since protocol v4, an unset (`NOT_SET`) bind
+/// marker — both of which are encoded as negative `[bytes]` lengths on the wire.
 pub struct CBytes {
-    bytes: Vec<u8>,
+    bytes: Option<Vec<u8>>,
+    not_set: bool,
 }
 
 impl CBytes {
     pub fn new(bytes: Vec<u8>) -> CBytes {
-        return CBytes { bytes: bytes };
+        return CBytes {
+            bytes: Some(bytes),
+            not_set: false,
+        };
     }
-    /// Converts `CBytes` into a plain array of bytes
-    pub fn into_plain(self) -> Vec<u8> {
+
+    /// Creates a `CBytes` that represents Cassandra `NULL` (`[bytes]` length `-1`).
+    pub fn new_null() -> CBytes {
+        return CBytes {
+            bytes: None,
+            not_set: false,
+        };
+    }
+
+    /// Creates a `CBytes` that represents an unset bind marker (`[bytes]` length
+    /// `-2`), meaning the existing value of a prepared statement parameter should be
+    /// left untouched.
+    pub fn new_not_set() -> CBytes {
+        return CBytes {
+            bytes: None,
+            not_set: true,
+        };
+    }
+
+    /// `true` if this value is Cassandra `NULL`.
+    pub fn is_null(&self) -> bool {
+        return self.bytes.is_none() && !self.not_set;
+    }
+
+    /// `true` if this value is an unset (`NOT_SET`) bind marker.
+    pub fn is_not_set(&self) -> bool {
+        return self.not_set;
+    }
+
+    /// Converts `CBytes` into a plain array of bytes, or `None` if it is `NULL` or
+    /// `NOT_SET`.
+    pub fn into_plain(self) -> Option<Vec<u8>> {
         return self.bytes;
     }
-    pub fn as_plain(&self) -> Vec<u8> {
+
+    pub fn as_plain(&self) -> Option<Vec<u8>> {
         return self.bytes.clone();
     }
+
+    /// Converts `CBytes` into an `Option<Vec<u8>>`, folding both `NULL` and
+    /// `NOT_SET` into `None`.
+    pub fn into_option(self) -> Option<Vec<u8>> {
+        return self.bytes;
+    }
 }
 
 impl FromCursor for CBytes {
     /// from_cursor gets Cursor who's position is set such that it should be a start of a [bytes].
     /// It reads required number of bytes and returns a CBytes
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CBytes {
-        let len: u64 = CInt::from_cursor(&mut cursor) as u64;
-        return CBytes { bytes: cursor_next_value(&mut cursor, len) };
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CBytes> {
+        let len: CInt = try!(CInt::from_cursor(&mut cursor));
+        match len {
+            NULL_INT_LEN => Ok(CBytes::new_null()),
+            NOT_SET_INT_LEN => Ok(CBytes::new_not_set()),
+            _ if len < 0 => {
+                Err(Error::from(ParseError::InvalidType {
+                    field: "CBytes length",
+                    reason: format!("{} is not a valid [bytes] length, NULL or NOT_SET marker",
+                                     len),
+                }))
+            }
+            _ => {
+                let body = try!(cursor_next_value(&mut cursor, len as u64, "CBytes body"));
+                Ok(CBytes::new(body))
+            }
+        }
     }
 }
 
@@ -295,11 +504,78 @@ impl FromCursor for CBytes {
 impl IntoBytes for CBytes {
     fn into_cbytes(&self) -> Vec<u8> {
         let mut v: Vec<u8> = vec![];
-        let l = self.bytes.len() as i64;
-        v.extend_from_slice(to_int(l).as_slice());
-        v.extend_from_slice(self.bytes.as_slice());
+        if self.not_set {
+            v.extend_from_slice(to_int(NOT_SET_INT_LEN as i64).as_slice());
+            return v;
+        }
+        match self.bytes {
+            Some(ref bytes) => {
+                let l = bytes.len() as i64;
+                v.extend_from_slice(to_int(l).as_slice());
+                v.extend_from_slice(bytes.as_slice());
+            }
+            None => {
+                v.extend_from_slice(to_int(NULL_INT_LEN as i64).as_slice());
+            }
+        }
         return v;
     }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        if self.not_set {
+            extend_be_i32(out, NOT_SET_INT_LEN as i32);
+            return;
+        }
+        match self.bytes {
+            Some(ref bytes) => {
+                extend_be_i32(out, bytes.len() as i32);
+                out.extend_from_slice(bytes.as_slice());
+            }
+            None => {
+                extend_be_i32(out, NULL_INT_LEN as i32);
+            }
+        }
+    }
+}
+
+/// Cassandra `[bytes map]`: a `[short]` count followed by that many pairs of a key
+/// `[string]` and a value `[bytes]`. Used by custom payload frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CBytesMap {
+    pub map: Map<String, CBytes>,
+}
+
+impl CBytesMap {
+    pub fn new(map: Map<String, CBytes>) -> CBytesMap {
+        return CBytesMap { map: map };
+    }
+}
+
+impl IntoBytes for CBytesMap {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut bytes = to_short(self.map.len() as u64);
+        for (key, value) in self.map.iter() {
+            bytes.extend_from_slice(CString::new(key.clone()).into_cbytes().as_slice());
+            bytes.extend_from_slice(value.into_cbytes().as_slice());
+        }
+        return bytes;
+    }
+}
+
+impl FromCursor for CBytesMap {
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CBytesMap> {
+        let len_bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "CBytesMap len"));
+        let len: u64 = try!(from_bytes(len_bytes));
+        // `Map::with_capacity` isn't available on `BTreeMap` (the `no_std` backend),
+        // so the count only drives the loop bound, not a capacity hint.
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = try!(CString::from_cursor(&mut cursor)).into_plain();
+            let value = try!(CBytes::from_cursor(&mut cursor));
+            map.insert(key, value);
+        }
+        return Ok(CBytesMap { map: map });
+    }
 }
 
 /// Cassandra short bytes
@@ -321,9 +597,10 @@ impl CBytesShort {
 impl FromCursor for CBytesShort {
     /// from_cursor gets Cursor who's position is set such that it should be a start of a [bytes].
     /// It reads required number of bytes and returns a CBytes
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CBytesShort {
-        let len: u64 = CIntShort::from_cursor(&mut cursor) as u64;
-        return CBytesShort { bytes: cursor_next_value(&mut cursor, len) };
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CBytesShort> {
+        let len: u64 = try!(CIntShort::from_cursor(&mut cursor)) as u64;
+        let body = try!(cursor_next_value(&mut cursor, len, "CBytesShort body"));
+        return Ok(CBytesShort { bytes: body });
     }
 }
 
@@ -336,6 +613,11 @@ impl IntoBytes for CBytesShort {
         v.extend_from_slice(self.bytes.as_slice());
         return v;
     }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        extend_be_u16(out, self.bytes.len() as u16);
+        out.extend_from_slice(self.bytes.as_slice());
+    }
 }
 
 
@@ -343,9 +625,9 @@ impl IntoBytes for CBytesShort {
 pub type CInt = i32;
 
 impl FromCursor for CInt {
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CInt {
-        let bytes = cursor_next_value(&mut cursor, INT_LEN as u64);
-        return from_bytes(bytes) as CInt;
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CInt> {
+        let bytes = try!(cursor_next_value(&mut cursor, INT_LEN as u64, "CInt"));
+        return Ok(try!(from_bytes(bytes)) as CInt);
     }
 }
 
@@ -353,54 +635,374 @@ impl FromCursor for CInt {
 pub type CIntShort = i16;
 
 impl FromCursor for CIntShort {
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CIntShort {
-        let bytes = cursor_next_value(&mut cursor, SHORT_LEN as u64);
-        return from_bytes(bytes) as CIntShort;
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CIntShort> {
+        let bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "CIntShort"));
+        return Ok(try!(from_bytes(bytes)) as CIntShort);
+    }
+}
+
+/// Number of extra bytes following the first byte of an unsigned `[vint]` encoding
+/// of `v`, i.e. the number of leading `1` bits the first byte will carry.
+fn u_vint_extra_bytes(v: u64) -> usize {
+    if v == 0 {
+        return 0;
+    }
+    let bits = 64 - v.leading_zeros() as usize;
+    for n in 0..8 {
+        if bits <= 7 + 7 * n {
+            return n;
+        }
+    }
+    8
+}
+
+/// Encodes `v` using Cassandra's unsigned vint format: the value is written in the
+/// fewest bytes needed, with the first byte's leading `1` bits counting the number
+/// of extra following bytes (an all-ones first byte means 8 extra bytes).
+pub fn to_u_vint(v: u64) -> Vec<u8> {
+    let n = u_vint_extra_bytes(v);
+
+    if n == 8 {
+        let mut bytes = Vec::with_capacity(9);
+        bytes.push(0xFF);
+        for i in (0..8).rev() {
+            bytes.push((v >> (8 * i)) as u8);
+        }
+        return bytes;
+    }
+
+    let data_bits = 7 - n;
+    let mask: u8 = if data_bits == 0 {
+        0
+    } else {
+        ((1u16 << data_bits) - 1) as u8
+    };
+    let prefix: u8 = if n == 0 {
+        0
+    } else {
+        (0xFFu8 << (8 - n)) as u8
+    };
+    let high = ((v >> (8 * n)) as u8) & mask;
+
+    let mut bytes = Vec::with_capacity(n + 1);
+    bytes.push(prefix | high);
+    for i in (0..n).rev() {
+        bytes.push((v >> (8 * i)) as u8);
+    }
+    return bytes;
+}
+
+/// Decodes an unsigned `[vint]` starting at the cursor's current position.
+pub fn from_u_vint<R: Read>(cursor: &mut R) -> CDRSResult<u64> {
+    let first_byte = try!(cursor_next_value(cursor, 1, "vint first byte"))[0];
+    let n = (!first_byte).leading_zeros() as usize;
+    if n == 0 {
+        return Ok(first_byte as u64);
+    }
+
+    let extra = try!(cursor_next_value(cursor, n as u64, "vint extra bytes"));
+    let mask: u8 = if n >= 8 {
+        0
+    } else {
+        ((1u16 << (7 - n)) - 1) as u8
+    };
+    let mut v: u64 = (first_byte & mask) as u64;
+    for b in extra.iter() {
+        v = (v << 8) | (*b as u64);
+    }
+    return Ok(v);
+}
+
+/// Maps a signed value onto the unsigned range so small negative and small
+/// positive numbers both encode to a small unsigned vint (ZigZag encoding).
+fn zigzag_encode(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Inverse of `zigzag_encode`.
+fn zigzag_decode(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Encodes `v` as a signed `[vint]`: ZigZag maps `v` onto `u64` and encodes the
+/// result as an unsigned vint. Used by `varint`, `decimal` scale, `duration` and
+/// some counters in protocol v4+.
+pub fn to_vint(v: i64) -> Vec<u8> {
+    return to_u_vint(zigzag_encode(v));
+}
+
+/// Decodes a signed `[vint]` starting at the cursor's current position.
+pub fn from_vint<R: Read>(cursor: &mut R) -> CDRSResult<i64> {
+    let v = try!(from_u_vint(cursor));
+    return Ok(zigzag_decode(v));
+}
+
+/// Cassandra signed `[vint]`, as used by `varint`, `decimal` scale, `duration` and
+/// some counters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CVint(i64);
+
+impl CVint {
+    pub fn new(v: i64) -> CVint {
+        return CVint(v);
+    }
+
+    pub fn into_plain(self) -> i64 {
+        return self.0;
+    }
+}
+
+impl IntoBytes for CVint {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return to_vint(self.0);
+    }
+}
+
+impl FromCursor for CVint {
+    fn from_cursor<R: Read>(cursor: &mut R) -> CDRSResult<CVint> {
+        return Ok(CVint(try!(from_vint(cursor))));
+    }
+}
+
+/// Cassandra unsigned `[vint]`, the raw encoding that signed `[vint]`s build on top
+/// of via ZigZag mapping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CUVint(u64);
+
+impl CUVint {
+    pub fn new(v: u64) -> CUVint {
+        return CUVint(v);
+    }
+
+    pub fn into_plain(self) -> u64 {
+        return self.0;
+    }
+}
+
+impl IntoBytes for CUVint {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return to_u_vint(self.0);
+    }
+}
+
+impl FromCursor for CUVint {
+    fn from_cursor<R: Read>(cursor: &mut R) -> CDRSResult<CUVint> {
+        return Ok(CUVint(try!(from_u_vint(cursor))));
+    }
+}
+
+/// Option ids of the native column types whose `[option]` encoding carries a
+/// trailing value. Every other scalar id (Ascii, Boolean, ...) has no trailing
+/// value at all, so `COption::value` is `None` for them.
+const CUSTOM_OPTION_ID: CIntShort = 0x0000;
+const LIST_OPTION_ID: CIntShort = 0x0020;
+const MAP_OPTION_ID: CIntShort = 0x0021;
+const SET_OPTION_ID: CIntShort = 0x0022;
+const UDT_OPTION_ID: CIntShort = 0x0030;
+const TUPLE_OPTION_ID: CIntShort = 0x0031;
+
+/// Trailing value of a `[option]`, shaped by its option id.
+#[derive(Debug, Clone, PartialEq)]
+pub enum COptionValue {
+    /// `Custom`: the fully qualified Java class name implementing the type.
+    Custom(CString),
+    /// `List`/`Set`: the nested `[option]` describing the element type.
+    Elem(Box<COption>),
+    /// `Map`: the nested `[option]`s describing the key and value types.
+    Map(Box<COption>, Box<COption>),
+    /// `UDT`: keyspace, type name, and the `[option]`-typed fields in declaration
+    /// order.
+    Udt {
+        keyspace: CString,
+        name: CString,
+        fields: Vec<(CString, COption)>,
+    },
+    /// `Tuple`: the `[option]`-typed fields in declaration order.
+    Tuple(Vec<COption>),
+}
+
+/// Cassandra `[option]`: a `[short]` option id, optionally followed by a value
+/// whose shape depends on that id. Used by result and prepared-statement metadata
+/// to describe the native type of a column; `List`/`Set`/`Map`/`UDT`/`Tuple`
+/// options nest further `[option]`s, which are decoded recursively so the cursor
+/// always ends up past the whole type description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct COption {
+    pub id: CIntShort,
+    pub value: Option<COptionValue>,
+}
+
+impl COption {
+    pub fn new(id: CIntShort, value: Option<COptionValue>) -> COption {
+        return COption {
+            id: id,
+            value: value,
+        };
+    }
+}
+
+impl IntoBytes for COption {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut bytes = to_short(self.id as u64);
+        match self.value {
+            Some(COptionValue::Custom(ref class_name)) => {
+                bytes.extend_from_slice(class_name.into_cbytes().as_slice());
+            }
+            Some(COptionValue::Elem(ref elem)) => {
+                bytes.extend_from_slice(elem.into_cbytes().as_slice());
+            }
+            Some(COptionValue::Map(ref key, ref value)) => {
+                bytes.extend_from_slice(key.into_cbytes().as_slice());
+                bytes.extend_from_slice(value.into_cbytes().as_slice());
+            }
+            Some(COptionValue::Udt { ref keyspace, ref name, ref fields }) => {
+                bytes.extend_from_slice(keyspace.into_cbytes().as_slice());
+                bytes.extend_from_slice(name.into_cbytes().as_slice());
+                bytes.extend_from_slice(to_short(fields.len() as u64).as_slice());
+                for &(ref field_name, ref field_type) in fields.iter() {
+                    bytes.extend_from_slice(field_name.into_cbytes().as_slice());
+                    bytes.extend_from_slice(field_type.into_cbytes().as_slice());
+                }
+            }
+            Some(COptionValue::Tuple(ref fields)) => {
+                bytes.extend_from_slice(to_short(fields.len() as u64).as_slice());
+                for field_type in fields.iter() {
+                    bytes.extend_from_slice(field_type.into_cbytes().as_slice());
+                }
+            }
+            None => {}
+        }
+        return bytes;
+    }
+}
+
+impl FromCursor for COption {
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<COption> {
+        let id = try!(CIntShort::from_cursor(&mut cursor));
+        let value = match id {
+            CUSTOM_OPTION_ID => Some(COptionValue::Custom(try!(CString::from_cursor(&mut cursor)))),
+            LIST_OPTION_ID | SET_OPTION_ID => {
+                Some(COptionValue::Elem(Box::new(try!(COption::from_cursor(&mut cursor)))))
+            }
+            MAP_OPTION_ID => {
+                let key = Box::new(try!(COption::from_cursor(&mut cursor)));
+                let value = Box::new(try!(COption::from_cursor(&mut cursor)));
+                Some(COptionValue::Map(key, value))
+            }
+            UDT_OPTION_ID => {
+                let keyspace = try!(CString::from_cursor(&mut cursor));
+                let name = try!(CString::from_cursor(&mut cursor));
+                // The field count is an unsigned `[short]` (0..=65535), not a
+                // `CIntShort`: a count >= 0x8000 is legal on the wire but would sign-
+                // extend to a huge `usize` if read as `i16`, so it's read as raw
+                // `[short]` bytes and never handed to `Vec::with_capacity` untrusted.
+                let n_bytes = try!(cursor_next_value(&mut cursor,
+                                                       SHORT_LEN as u64,
+                                                       "COption UDT field count"));
+                let n: u64 = try!(from_bytes(n_bytes));
+                let mut fields = Vec::new();
+                for _ in 0..n {
+                    let field_name = try!(CString::from_cursor(&mut cursor));
+                    let field_type = try!(COption::from_cursor(&mut cursor));
+                    fields.push((field_name, field_type));
+                }
+                Some(COptionValue::Udt {
+                    keyspace: keyspace,
+                    name: name,
+                    fields: fields,
+                })
+            }
+            TUPLE_OPTION_ID => {
+                // See the UDT branch above: an unsigned `[short]` count, read
+                // without trusting it as an allocation size.
+                let n_bytes = try!(cursor_next_value(&mut cursor,
+                                                       SHORT_LEN as u64,
+                                                       "COption Tuple field count"));
+                let n: u64 = try!(from_bytes(n_bytes));
+                let mut fields = Vec::new();
+                for _ in 0..n {
+                    fields.push(try!(COption::from_cursor(&mut cursor)));
+                }
+                Some(COptionValue::Tuple(fields))
+            }
+            _ => None,
+        };
+        return Ok(COption::new(id, value));
     }
 }
 
 // Use extended Rust Vec<u8> as Cassandra [bytes]
 impl FromBytes for Vec<u8> {
-    fn from_bytes(bytes: Vec<u8>) -> Vec<u8> {
+    fn from_bytes(bytes: Vec<u8>) -> CDRSResult<Vec<u8>> {
         let mut cursor = Cursor::new(bytes);
-        let len_bytes = cursor_next_value(&mut cursor, SHORT_LEN as u64);
-        let len: u64 = from_bytes(len_bytes);
-        return cursor_next_value(&mut cursor, len);
+        let len_bytes = try!(cursor_next_value(&mut cursor, SHORT_LEN as u64, "Vec<u8> len"));
+        let len: u64 = try!(from_bytes(len_bytes));
+        return cursor_next_value(&mut cursor, len, "Vec<u8> body");
     }
 }
 
 /// The structure wich represets Cassandra [inet]
 /// (https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L222).
+/// Only available with the `std` feature, since it is built on `std::net::SocketAddr`.
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct CInet {
     pub addr: SocketAddr,
 }
 
+#[cfg(feature = "std")]
 impl FromCursor for CInet {
-    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CInet {
-        let n = CIntShort::from_cursor(&mut cursor);
-        let bytes = cursor_next_value(&mut cursor, n as u64);
-        let ip = decode_inet(bytes).unwrap();
-        let port = CInt::from_cursor(&mut cursor);
+    fn from_cursor<R: Read>(mut cursor: &mut R) -> CDRSResult<CInet> {
+        let n = try!(CIntShort::from_cursor(&mut cursor));
+        let bytes = try!(cursor_next_value(&mut cursor, n as u64, "CInet addr"));
+        let ip = try!(decode_inet(bytes));
+        let port = try!(CInt::from_cursor(&mut cursor));
         let socket_addr = SocketAddr::new(ip, port as u16);
 
-        CInet { addr: socket_addr }
+        Ok(CInet { addr: socket_addr })
     }
 }
 
-pub fn cursor_next_value(mut cursor: &mut Cursor<Vec<u8>>, len: u64) -> Vec<u8> {
-    let l = len as usize;
-    let current_position = cursor.position();
-    let mut buff: Vec<u8> = Vec::with_capacity(l);
-    unsafe {
-        buff.set_len(l);
-    }
-    if let Err(err) = cursor.read(&mut buff) {
-        error!("Read from cursor error: {}", err);
-        panic!(err);
+/// Upper bound on how much `cursor_next_value` will allocate ahead of what it has
+/// actually confirmed is available on `source`. A declared `[int]`/`[long string]`
+/// length is attacker-controlled and can claim up to ~2 GB; growing the output
+/// buffer in bounded chunks instead of allocating `len` up front means a single
+/// malformed frame can be rejected with `UnexpectedEof` instead of exhausting
+/// memory before the first byte is even read.
+const CURSOR_NEXT_VALUE_CHUNK: usize = 8192;
+
+/// Reads exactly `len` bytes from `source`, advancing it by `len`. Returns
+/// `ParseError::UnexpectedEof` (naming `field`, with how many bytes were actually
+/// read before the source ran dry) instead of panicking on a short read. Works
+/// over any `Read` source, not just an in-memory `Cursor`. Grows its buffer in
+/// `CURSOR_NEXT_VALUE_CHUNK`-sized steps rather than allocating `len` bytes up
+/// front, so a bogus, oversized `len` is bounded by what `source` actually has to
+/// offer rather than by the claim itself.
+pub fn cursor_next_value<R: Read>(source: &mut R,
+                                   len: u64,
+                                   field: &'static str)
+                                   -> CDRSResult<Vec<u8>> {
+    let mut buff: Vec<u8> = Vec::new();
+    let mut remaining = len;
+    let mut chunk = [0u8; CURSOR_NEXT_VALUE_CHUNK];
+    while remaining > 0 {
+        let want = if remaining < CURSOR_NEXT_VALUE_CHUNK as u64 {
+            remaining as usize
+        } else {
+            CURSOR_NEXT_VALUE_CHUNK
+        };
+        let n = try!(source.read(&mut chunk[..want]));
+        if n == 0 {
+            return Err(Error::from(ParseError::UnexpectedEof {
+                field: field,
+                expected: len,
+                available: buff.len() as u64,
+            }));
+        }
+        buff.extend_from_slice(&chunk[..n]);
+        remaining -= n as u64;
     }
-    cursor.set_position(current_position + len);
-    return buff;
+    return Ok(buff);
 }
 
 
@@ -441,14 +1043,27 @@ mod tests {
         assert_eq!(cstring.into_cbytes(), vec![0, 3, 102, 111, 111]);
     }
 
+    #[test]
+    fn test_cstring_serialize() {
+        let cstring = CString::new("foo".to_string());
+        let mut out = vec![9];
+        cstring.serialize(&mut out);
+        assert_eq!(out, vec![9, 0, 3, 102, 111, 111]);
+    }
+
     #[test]
     fn test_cstring_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 3, 102, 111, 111, 0]);
-        let cstring = CString::from_cursor(&mut cursor);
-        println!("{:?}", &cursor);
+        let cstring = CString::from_cursor(&mut cursor).unwrap();
         assert_eq!(cstring.as_str(), "foo");
     }
 
+    #[test]
+    fn test_cstring_from_cursor_truncated() {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 3, 102, 111]);
+        assert!(CString::from_cursor(&mut cursor).is_err());
+    }
+
     // CStringLong
     #[test]
     fn test_cstringlong_new() {
@@ -483,8 +1098,7 @@ mod tests {
     #[test]
     fn test_cstringlong_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 0, 0, 3, 102, 111, 111, 0]);
-        let cstring = CStringLong::from_cursor(&mut cursor);
-        println!("{:?}", &cursor);
+        let cstring = CStringLong::from_cursor(&mut cursor).unwrap();
         assert_eq!(cstring.as_str(), "foo");
     }
 
@@ -493,7 +1107,7 @@ mod tests {
     fn test_cstringlist() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 2, 0, 3, 102, 111, 111, 0, 3, 102,
                                                            111, 111]);
-        let list = CStringList::from_cursor(&mut cursor);
+        let list = CStringList::from_cursor(&mut cursor).unwrap();
         let plain = list.into_plain();
         assert_eq!(plain.len(), 2);
         for s in plain.iter() {
@@ -511,14 +1125,14 @@ mod tests {
     #[test]
     fn test_cbytes_into_plain() {
         let cbytes = CBytes::new(vec![1, 2, 3]);
-        assert_eq!(cbytes.into_plain(), vec![1, 2, 3]);
+        assert_eq!(cbytes.into_plain(), Some(vec![1, 2, 3]));
     }
 
     #[test]
     fn test_cbytes_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 0, 0, 3, 1, 2, 3]);
-        let cbytes = CBytes::from_cursor(&mut cursor);
-        assert_eq!(cbytes.into_plain(), vec![1, 2, 3]);
+        let cbytes = CBytes::from_cursor(&mut cursor).unwrap();
+        assert_eq!(cbytes.into_plain(), Some(vec![1, 2, 3]));
     }
 
     #[test]
@@ -528,6 +1142,44 @@ mod tests {
         assert_eq!(cbytes.into_cbytes(), vec![0, 0, 0, 3, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_cbytes_serialize_matches_into_cbytes() {
+        let cbytes = CBytes::new(vec![1, 2, 3]);
+        let mut out = vec![];
+        cbytes.serialize(&mut out);
+        assert_eq!(out, cbytes.into_cbytes());
+
+        let null = CBytes::new_null();
+        let mut out = vec![];
+        null.serialize(&mut out);
+        assert_eq!(out, null.into_cbytes());
+    }
+
+    #[test]
+    fn test_cbytes_null() {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![255, 255, 255, 255]);
+        let cbytes = CBytes::from_cursor(&mut cursor).unwrap();
+        assert!(cbytes.is_null());
+        assert!(!cbytes.is_not_set());
+        assert_eq!(cbytes.into_cbytes(), vec![255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_cbytes_not_set() {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![255, 255, 255, 254]);
+        let cbytes = CBytes::from_cursor(&mut cursor).unwrap();
+        assert!(cbytes.is_not_set());
+        assert!(!cbytes.is_null());
+        assert_eq!(cbytes.into_cbytes(), vec![255, 255, 255, 254]);
+        assert_eq!(cbytes.into_option(), None);
+    }
+
+    #[test]
+    fn test_cbytes_invalid_negative_len() {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![255, 255, 255, 253]);
+        assert!(CBytes::from_cursor(&mut cursor).is_err());
+    }
+
     // CBytesShort
     #[test]
     fn test_cbytesshort_new() {
@@ -544,7 +1196,7 @@ mod tests {
     #[test]
     fn test_cbytesshort_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 3, 1, 2, 3]);
-        let cbytes = CBytesShort::from_cursor(&mut cursor);
+        let cbytes = CBytesShort::from_cursor(&mut cursor).unwrap();
         assert_eq!(cbytes.into_plain(), vec![1, 2, 3]);
     }
 
@@ -559,7 +1211,7 @@ mod tests {
     #[test]
     fn test_cint_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 0, 0, 5]);
-        let i = CInt::from_cursor(&mut cursor);
+        let i = CInt::from_cursor(&mut cursor).unwrap();
         assert_eq!(i, 5);
     }
 
@@ -567,7 +1219,7 @@ mod tests {
     #[test]
     fn test_cintshort_from_cursor() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 5]);
-        let i = CIntShort::from_cursor(&mut cursor);
+        let i = CIntShort::from_cursor(&mut cursor).unwrap();
         assert_eq!(i, 5);
     }
 
@@ -576,8 +1228,210 @@ mod tests {
     fn test_cursor_next_value() {
         let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 1, 2, 3, 4]);
         let l: u64 = 3;
-        let val = cursor_next_value(&mut cursor, l);
+        let val = cursor_next_value(&mut cursor, l, "test").unwrap();
         assert_eq!(val, vec![0, 1, 2]);
     }
 
+    #[test]
+    fn test_cursor_next_value_truncated() {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 1]);
+        assert!(cursor_next_value(&mut cursor, 3, "test").is_err());
+    }
+
+    #[test]
+    fn test_cursor_next_value_spans_multiple_chunks() {
+        let data: Vec<u8> = (0..(CURSOR_NEXT_VALUE_CHUNK * 2 + 7))
+            .map(|i| (i % 256) as u8)
+            .collect();
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(data.clone());
+        let val = cursor_next_value(&mut cursor, data.len() as u64, "test").unwrap();
+        assert_eq!(val, data);
+    }
+
+    #[test]
+    fn test_cursor_next_value_oversized_len_fails_without_large_alloc() {
+        // A declared length far larger than what `source` actually holds must be
+        // rejected as soon as the source runs dry, not by allocating the full
+        // (attacker-controlled) length up front.
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 1, 2]);
+        assert!(cursor_next_value(&mut cursor, 1 << 31, "test").is_err());
+    }
+
+    #[test]
+    fn test_from_cursor_over_borrowed_slice() {
+        // FromCursor is generic over any `Read`, not just `Cursor<Vec<u8>>`.
+        let raw: &[u8] = &[0, 3, 102, 111, 111];
+        let mut source = raw;
+        let cstring = CString::from_cursor(&mut source).unwrap();
+        assert_eq!(cstring.as_str(), "foo");
+    }
+
+    // vint
+    #[test]
+    fn test_u_vint_roundtrip() {
+        let values: Vec<u64> = vec![0, 1, 127, 128, 16383, 16384, 2097151, 2097152,
+                                     u32::max_value() as u64, u64::max_value()];
+        for v in values {
+            let bytes = to_u_vint(v);
+            let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+            assert_eq!(from_u_vint(&mut cursor).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_vint_roundtrip() {
+        let values: Vec<i64> = vec![0, 1, -1, 63, -64, 64, -65, i32::max_value() as i64,
+                                     i32::min_value() as i64, i64::max_value(), i64::min_value()];
+        for v in values {
+            let bytes = to_vint(v);
+            let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+            assert_eq!(from_vint(&mut cursor).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_u_vint_single_byte_encoding() {
+        assert_eq!(to_u_vint(0), vec![0]);
+        assert_eq!(to_u_vint(127), vec![127]);
+    }
+
+    #[test]
+    fn test_cvint_into_cbytes_and_from_cursor() {
+        let cvint = CVint::new(-12345);
+        let bytes = cvint.into_cbytes();
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+        let parsed = CVint::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed.into_plain(), -12345);
+    }
+
+    // CStringMap
+    #[test]
+    fn test_cstringmap_roundtrip() {
+        let mut map = Map::new();
+        map.insert("CQL_VERSION".to_string(), "3.0.0".to_string());
+        let cmap = CStringMap::new(map.clone());
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(cmap.into_cbytes());
+        let parsed = CStringMap::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed.map, map);
+    }
+
+    // CStringMultimap
+    #[test]
+    fn test_cstringmultimap_roundtrip() {
+        let mut map = Map::new();
+        map.insert("COMPRESSION".to_string(),
+                    vec!["snappy".to_string(), "lz4".to_string()]);
+        let cmap = CStringMultimap::new(map.clone());
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(cmap.into_cbytes());
+        let parsed = CStringMultimap::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed.map, map);
+    }
+
+    // CBytesMap
+    #[test]
+    fn test_cbytesmap_roundtrip() {
+        let mut map = Map::new();
+        map.insert("payload".to_string(), CBytes::new(vec![1, 2, 3]));
+        let cmap = CBytesMap::new(map.clone());
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(cmap.into_cbytes());
+        let parsed = CBytesMap::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed.map, map);
+    }
+
+    // COption
+    #[test]
+    fn test_coption_without_value() {
+        let option = COption::new(0x0001, None);
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_with_custom_value() {
+        let option = COption::new(0x0000,
+                                   Some(COptionValue::Custom(CString::new("my.custom.Type"
+                                       .to_string()))));
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_list_nested() {
+        let elem = COption::new(0x0009, None); // Int
+        let option = COption::new(LIST_OPTION_ID, Some(COptionValue::Elem(Box::new(elem))));
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_map_nested() {
+        let key = COption::new(0x000D, None); // Varchar
+        let value = COption::new(0x0009, None); // Int
+        let option = COption::new(MAP_OPTION_ID,
+                                   Some(COptionValue::Map(Box::new(key), Box::new(value))));
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_udt_nested() {
+        let field = (CString::new("id".to_string()), COption::new(0x0009, None));
+        let option = COption::new(UDT_OPTION_ID,
+                                   Some(COptionValue::Udt {
+                                       keyspace: CString::new("ks".to_string()),
+                                       name: CString::new("my_type".to_string()),
+                                       fields: vec![field],
+                                   }));
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_udt_high_bit_count_does_not_panic() {
+        // A field count >= 0x8000 is a legal unsigned [short] but would sign-extend
+        // to a huge usize if misread as a CIntShort; it must fail with a
+        // ParseError, not panic in Vec::with_capacity.
+        let mut bytes = to_short(UDT_OPTION_ID as u64);
+        bytes.extend_from_slice(CString::new("ks".to_string()).into_cbytes().as_slice());
+        bytes.extend_from_slice(CString::new("my_type".to_string()).into_cbytes().as_slice());
+        bytes.extend_from_slice(&[0x80, 0x00]); // field count 0x8000 = 32768
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+        assert!(COption::from_cursor(&mut cursor).is_err());
+    }
+
+    #[test]
+    fn test_coption_tuple_high_bit_count_does_not_panic() {
+        let mut bytes = to_short(TUPLE_OPTION_ID as u64);
+        bytes.extend_from_slice(&[0xFF, 0xFF]); // field count 0xFFFF = 65535
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+        assert!(COption::from_cursor(&mut cursor).is_err());
+    }
+
+    #[test]
+    fn test_coption_tuple_nested() {
+        let fields = vec![COption::new(0x0009, None), COption::new(0x000D, None)];
+        let option = COption::new(TUPLE_OPTION_ID, Some(COptionValue::Tuple(fields)));
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(option.into_cbytes());
+        let parsed = COption::from_cursor(&mut cursor).unwrap();
+        assert_eq!(parsed, option);
+    }
+
+    #[test]
+    fn test_coption_leaves_cursor_past_nested_value() {
+        let elem = COption::new(0x0009, None);
+        let option = COption::new(LIST_OPTION_ID, Some(COptionValue::Elem(Box::new(elem))));
+        let mut bytes = option.into_cbytes();
+        bytes.extend_from_slice(&[0xAB, 0xCD]);
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+        let _ = COption::from_cursor(&mut cursor).unwrap();
+        let mut rest = vec![];
+        cursor.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, vec![0xAB, 0xCD]);
+    }
+
 }